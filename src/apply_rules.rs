@@ -18,7 +18,10 @@
 
 use crate::collection::{CollectionWithId, Id};
 use crate::model::Collections;
-use crate::objects::Codes;
+use crate::objects::{
+    Codes, CommercialMode, Company, Line, Network, PhysicalMode, Route, StopArea, StopPoint,
+    VehicleJourney,
+};
 use crate::utils::{Report, ReportType};
 use crate::Result;
 use csv;
@@ -34,6 +37,11 @@ enum ObjectType {
     Route,
     StopPoint,
     StopArea,
+    Network,
+    Company,
+    PhysicalMode,
+    CommercialMode,
+    VehicleJourney,
 }
 impl ObjectType {
     pub fn as_str(&self) -> &'static str {
@@ -42,8 +50,41 @@ impl ObjectType {
             ObjectType::Route => "route",
             ObjectType::StopPoint => "stop_point",
             ObjectType::StopArea => "stop_area",
+            ObjectType::Network => "network",
+            ObjectType::Company => "company",
+            ObjectType::PhysicalMode => "physical_mode",
+            ObjectType::CommercialMode => "commercial_mode",
+            ObjectType::VehicleJourney => "vehicle_journey",
         }
     }
+
+    const ALL: &'static [ObjectType] = &[
+        ObjectType::Line,
+        ObjectType::Route,
+        ObjectType::StopPoint,
+        ObjectType::StopArea,
+        ObjectType::Network,
+        ObjectType::Company,
+        ObjectType::PhysicalMode,
+        ObjectType::CommercialMode,
+        ObjectType::VehicleJourney,
+    ];
+}
+
+/// What a complementary-code rule does to the matched object: `add`,
+/// `remove` or `replace` a code. Defaults to `Add` so existing rule files written
+/// before this column existed keep behaving the way they always have.
+#[derive(Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum RuleAction {
+    Add,
+    Remove,
+    Replace,
+}
+impl Default for RuleAction {
+    fn default() -> Self {
+        RuleAction::Add
+    }
 }
 
 #[derive(Deserialize, Debug, Ord, Eq, PartialOrd, PartialEq, Clone)]
@@ -52,49 +93,212 @@ struct ComplementaryCode {
     object_id: String,
     object_system: String,
     object_code: String,
+    #[serde(default)]
+    action: RuleAction,
 }
 
-fn read_complementary_code_rules_files<P: AsRef<Path>>(
+#[derive(Deserialize, Debug, Ord, Eq, PartialOrd, PartialEq, Clone)]
+struct ObjectPropertyRule {
+    object_type: ObjectType,
+    object_id: String,
+    object_property: String,
+    property_value: String,
+}
+
+/// Wraps the CSV file list for complementary-code rules so it can't be
+/// passed to `apply_rules` in the wrong parameter slot as a plain
+/// `Vec<PathBuf>` could be.
+pub struct ComplementaryCodeRulesFiles(pub Vec<PathBuf>);
+impl From<Vec<PathBuf>> for ComplementaryCodeRulesFiles {
+    fn from(files: Vec<PathBuf>) -> Self {
+        ComplementaryCodeRulesFiles(files)
+    }
+}
+
+/// Wraps the CSV file list for object-property rules; see
+/// `ComplementaryCodeRulesFiles`.
+pub struct ObjectPropertyRulesFiles(pub Vec<PathBuf>);
+impl From<Vec<PathBuf>> for ObjectPropertyRulesFiles {
+    fn from(files: Vec<PathBuf>) -> Self {
+        ObjectPropertyRulesFiles(files)
+    }
+}
+
+/// Lets an object type opt into the `object_property` rule kind without
+/// `apply_rules` having to know anything about its fields.
+pub trait Properties {
+    /// Sets `property` to `value`, returning `Err(())` if `property` isn't
+    /// one this object knows how to edit.
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()>;
+}
+
+impl Properties for Network {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            "timezone" => self.timezone = Some(value.to_string()),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for Company {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for PhysicalMode {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for CommercialMode {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for Line {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            "commercial_mode_id" => self.commercial_mode_id = value.to_string(),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for Route {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for StopArea {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            "timezone" => self.timezone = Some(value.to_string()),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for StopPoint {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            "timezone" => self.timezone = Some(value.to_string()),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+impl Properties for VehicleJourney {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "headsign" => self.headsign = Some(value.to_string()),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+/// Reads and deduplicates exact-duplicate rows, but keeps the rules in the
+/// order they were written: a rule's `action` (add/remove/replace) makes the
+/// order rules are applied in semantically meaningful, so we can't just
+/// hand the result back sorted by `Ord` the way a plain dedup-via-`BTreeSet`
+/// would.
+fn read_rules_file<T, P>(
     rule_files: Vec<P>,
+    report_type: ReportType,
     report: &mut Report,
-) -> Result<Vec<ComplementaryCode>> {
-    info!("Reading complementary code rules.");
-    let mut codes = BTreeSet::new();
+) -> Result<Vec<T>>
+where
+    T: Ord + Clone + for<'de> ::serde::Deserialize<'de>,
+    P: AsRef<Path>,
+{
+    let had_files = !rule_files.is_empty();
+    let mut seen = BTreeSet::new();
+    let mut rules = Vec::new();
     for rule_path in rule_files {
         let path = rule_path.as_ref();
         let mut rdr = csv::Reader::from_path(&path).with_context(ctx_from_path!(path))?;
-        for c in rdr.deserialize() {
-            let c: ComplementaryCode = match c {
+        for r in rdr.deserialize() {
+            let r: T = match r {
                 Ok(val) => val,
                 Err(e) => {
-                    report.add_warning(
+                    report.add_error(
                         format!("Error reading {:?}: {}", path.file_name().unwrap(), e),
-                        ReportType::ComplementaryCodeRulesRead,
+                        report_type,
                     );
                     continue;
                 }
             };
-            codes.insert(c);
+            if seen.insert(r.clone()) {
+                rules.push(r);
+            }
         }
     }
-    Ok(codes.into_iter().collect())
+    if had_files {
+        report.add_info(format!("Read {} rule(s)", rules.len()), report_type);
+    }
+    Ok(rules)
+}
+
+fn read_complementary_code_rules_files<P: AsRef<Path>>(
+    rule_files: Vec<P>,
+    report: &mut Report,
+) -> Result<Vec<ComplementaryCode>> {
+    info!("Reading complementary code rules.");
+    read_rules_file(rule_files, ReportType::ComplementaryCodeRulesRead, report)
 }
 
-fn insert_code<T>(
+fn read_object_property_rules_files<P: AsRef<Path>>(
+    rule_files: Vec<P>,
+    report: &mut Report,
+) -> Result<Vec<ObjectPropertyRule>> {
+    info!("Reading object property rules.");
+    read_rules_file(rule_files, ReportType::ObjectRulesRead, report)
+}
+
+fn apply_code_rule<T>(
     collection: &mut CollectionWithId<T>,
-    code: ComplementaryCode,
+    rule: ComplementaryCode,
     report: &mut Report,
 ) where
     T: Codes + Id<T>,
 {
-    let idx = match collection.get_idx(&code.object_id) {
+    let idx = match collection.get_idx(&rule.object_id) {
         Some(idx) => idx,
         None => {
             report.add_warning(
                 format!(
-                    "Error inserting code: object_codes.txt: object={},  object_id={} not found",
-                    code.object_type.as_str(),
-                    code.object_id
+                    "Error inserting code: object_codes.txt: object={}, object_id={} not found",
+                    rule.object_type.as_str(),
+                    rule.object_id
                 ),
                 ReportType::ComplementaryObjectNotFound,
             );
@@ -102,40 +306,368 @@ fn insert_code<T>(
         }
     };
 
-    collection
-        .index_mut(idx)
-        .codes_mut()
-        .insert((code.object_system, code.object_code));
+    let codes = collection.index_mut(idx).codes_mut();
+    let code = (rule.object_system, rule.object_code);
+    match rule.action {
+        RuleAction::Add => {
+            codes.insert(code);
+        }
+        RuleAction::Remove => {
+            if !codes.remove(&code) {
+                report.add_warning(
+                    format!(
+                        "Error removing code: object_codes.txt: object={}, object_id={}, \
+                         code {:?} not found",
+                        rule.object_type.as_str(),
+                        rule.object_id,
+                        code
+                    ),
+                    ReportType::NoOpRemoval,
+                );
+            }
+        }
+        RuleAction::Replace => {
+            codes.retain(|(system, _)| *system != code.0);
+            codes.insert(code);
+        }
+    }
+}
+
+fn apply_property_rule<T>(
+    collection: &mut CollectionWithId<T>,
+    rule: ObjectPropertyRule,
+    report: &mut Report,
+) where
+    T: Properties + Id<T>,
+{
+    let idx = match collection.get_idx(&rule.object_id) {
+        Some(idx) => idx,
+        None => {
+            report.add_warning(
+                format!(
+                    "Error updating property: object_properties.txt: object={}, object_id={} not found",
+                    rule.object_type.as_str(),
+                    rule.object_id
+                ),
+                ReportType::ObjectNotFound,
+            );
+            return;
+        }
+    };
+
+    let object = collection.index_mut(idx);
+    if object
+        .set_property(&rule.object_property, &rule.property_value)
+        .is_err()
+    {
+        report.add_warning(
+            format!(
+                "Error updating property: object_properties.txt: object={}, object_id={}, \
+                 unknown property {:?}",
+                rule.object_type.as_str(),
+                rule.object_id,
+                rule.object_property
+            ),
+            ReportType::UnknownPropertyName,
+        );
+    }
+}
+
+type CodeRuleFn = fn(&mut Collections, ComplementaryCode, &mut Report);
+type PropertyRuleFn = fn(&mut Collections, ObjectPropertyRule, &mut Report);
+
+/// One entry per object type a rule can target, registered here and nowhere
+/// else: adding a new object type to the rules engine means adding a row to
+/// this table, not touching the dispatch loops in `apply_rules`.
+const OBJECT_TYPE_HANDLERS: &[(ObjectType, CodeRuleFn, PropertyRuleFn)] = &[
+    (
+        ObjectType::Line,
+        |c, r, rep| apply_code_rule(&mut c.lines, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.lines, r, rep),
+    ),
+    (
+        ObjectType::Route,
+        |c, r, rep| apply_code_rule(&mut c.routes, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.routes, r, rep),
+    ),
+    (
+        ObjectType::StopPoint,
+        |c, r, rep| apply_code_rule(&mut c.stop_points, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.stop_points, r, rep),
+    ),
+    (
+        ObjectType::StopArea,
+        |c, r, rep| apply_code_rule(&mut c.stop_areas, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.stop_areas, r, rep),
+    ),
+    (
+        ObjectType::Network,
+        |c, r, rep| apply_code_rule(&mut c.networks, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.networks, r, rep),
+    ),
+    (
+        ObjectType::Company,
+        |c, r, rep| apply_code_rule(&mut c.companies, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.companies, r, rep),
+    ),
+    (
+        ObjectType::PhysicalMode,
+        |c, r, rep| apply_code_rule(&mut c.physical_modes, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.physical_modes, r, rep),
+    ),
+    (
+        ObjectType::CommercialMode,
+        |c, r, rep| apply_code_rule(&mut c.commercial_modes, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.commercial_modes, r, rep),
+    ),
+    (
+        ObjectType::VehicleJourney,
+        |c, r, rep| apply_code_rule(&mut c.vehicle_journeys, r, rep),
+        |c, r, rep| apply_property_rule(&mut c.vehicle_journeys, r, rep),
+    ),
+];
+
+fn dispatch_code_rule(collections: &mut Collections, rule: ComplementaryCode, report: &mut Report) {
+    let (_, apply, _) = OBJECT_TYPE_HANDLERS
+        .iter()
+        .find(|(object_type, _, _)| *object_type == rule.object_type)
+        .expect("every ObjectType variant has an entry in OBJECT_TYPE_HANDLERS");
+    apply(collections, rule, report);
+}
+
+fn dispatch_property_rule(
+    collections: &mut Collections,
+    rule: ObjectPropertyRule,
+    report: &mut Report,
+) {
+    let (_, _, apply) = OBJECT_TYPE_HANDLERS
+        .iter()
+        .find(|(object_type, _, _)| *object_type == rule.object_type)
+        .expect("every ObjectType variant has an entry in OBJECT_TYPE_HANDLERS");
+    apply(collections, rule, report);
 }
 
 /// Applying rules
 ///
-/// `complementary_code_rules_files` Csv files containing codes to add for certain objects
+/// `complementary_code_rules_files` Csv files containing codes to add,
+/// remove or replace on certain objects
+///
+/// `object_property_rules_files` Csv files containing properties to
+/// overwrite on certain objects
+///
+/// The two file lists are wrapped in distinct types (`ComplementaryCodeRulesFiles`,
+/// `ObjectPropertyRulesFiles`) rather than passed as plain `Vec<PathBuf>` so
+/// swapping them at a call site is a compile error, not a silent misroute.
+///
+/// Returns the [`Report`] so the caller can check `Report::has_errors()` and
+/// set a non-zero exit code, in addition to the JSON dumped at `report_path`.
 pub fn apply_rules(
     collections: &mut Collections,
-    complementary_code_rules_files: Vec<PathBuf>,
+    complementary_code_rules_files: ComplementaryCodeRulesFiles,
+    object_property_rules_files: ObjectPropertyRulesFiles,
     report_path: PathBuf,
-) -> Result<()> {
+) -> Result<Report> {
     info!("Applying rules...");
     let mut report = Report::default();
-    let codes = read_complementary_code_rules_files(complementary_code_rules_files, &mut report)?;
 
+    let codes =
+        read_complementary_code_rules_files(complementary_code_rules_files.0, &mut report)?;
     for code in codes {
-        match code.object_type {
-            ObjectType::Line => insert_code(&mut collections.lines, code, &mut report),
-            ObjectType::Route => insert_code(&mut collections.routes, code, &mut report),
-            ObjectType::StopPoint => insert_code(&mut collections.stop_points, code, &mut report),
-            ObjectType::StopArea => insert_code(&mut collections.stop_areas, code, &mut report),
-        }
+        dispatch_code_rule(collections, code, &mut report);
+    }
+
+    let properties =
+        read_object_property_rules_files(object_property_rules_files.0, &mut report)?;
+    for property in properties {
+        dispatch_property_rule(collections, property, &mut report);
     }
 
     let serialized_report = serde_json::to_string_pretty(&report)?;
     fs::write(report_path, serialized_report)?;
-    Ok(())
+    Ok(report)
 }
 
 #[cfg(test)]
 mod tests {
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone)]
+struct TestObj {
+    id: String,
+    name: String,
+    codes: BTreeSet<(String, String)>,
+}
+
+impl crate::collection::Id<TestObj> for TestObj {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl crate::objects::Codes for TestObj {
+    fn codes(&self) -> &BTreeSet<(String, String)> {
+        &self.codes
+    }
+    fn codes_mut(&mut self) -> &mut BTreeSet<(String, String)> {
+        &mut self.codes
+    }
+}
+
+impl super::Properties for TestObj {
+    fn set_property(&mut self, property: &str, value: &str) -> ::std::result::Result<(), ()> {
+        match property {
+            "name" => self.name = value.to_string(),
+            _ => return Err(()),
+        }
+        Ok(())
+    }
+}
+
+fn test_collection() -> crate::collection::CollectionWithId<TestObj> {
+    crate::collection::CollectionWithId::new(vec![TestObj {
+        id: "obj1".to_string(),
+        name: "Initial".to_string(),
+        codes: vec![("sys_a".to_string(), "code_a".to_string())]
+            .into_iter()
+            .collect(),
+    }])
+    .unwrap()
+}
+
+fn code_rule(action: super::RuleAction, system: &str, code: &str) -> super::ComplementaryCode {
+    super::ComplementaryCode {
+        object_type: super::ObjectType::Line,
+        object_id: "obj1".to_string(),
+        object_system: system.to_string(),
+        object_code: code.to_string(),
+        action,
+    }
+}
+
+#[test]
+fn apply_code_rule_add_inserts_code() {
+    let mut collection = test_collection();
+    let mut report = super::Report::default();
+
+    super::apply_code_rule(&mut collection, code_rule(super::RuleAction::Add, "sys_b", "code_b"), &mut report);
+
+    let idx = collection.get_idx("obj1").unwrap();
+    assert!(collection
+        .index_mut(idx)
+        .codes
+        .contains(&("sys_b".to_string(), "code_b".to_string())));
+    assert!(report.entries.is_empty());
+}
+
+#[test]
+fn apply_code_rule_remove_deletes_existing_code() {
+    let mut collection = test_collection();
+    let mut report = super::Report::default();
+
+    super::apply_code_rule(&mut collection, code_rule(super::RuleAction::Remove, "sys_a", "code_a"), &mut report);
+
+    let idx = collection.get_idx("obj1").unwrap();
+    assert!(collection.index_mut(idx).codes.is_empty());
+    assert!(report.entries.is_empty());
+}
+
+#[test]
+fn apply_code_rule_remove_missing_code_warns_no_op() {
+    let mut collection = test_collection();
+    let mut report = super::Report::default();
+
+    super::apply_code_rule(&mut collection, code_rule(super::RuleAction::Remove, "sys_z", "code_z"), &mut report);
+
+    let idx = collection.get_idx("obj1").unwrap();
+    assert_eq!(collection.index_mut(idx).codes.len(), 1);
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].report_type, super::ReportType::NoOpRemoval);
+}
+
+#[test]
+fn apply_code_rule_replace_drops_other_codes_for_same_system() {
+    let mut collection = test_collection();
+    {
+        let idx = collection.get_idx("obj1").unwrap();
+        collection
+            .index_mut(idx)
+            .codes
+            .insert(("sys_a".to_string(), "old_code".to_string()));
+        collection
+            .index_mut(idx)
+            .codes
+            .insert(("sys_b".to_string(), "untouched".to_string()));
+    }
+    let mut report = super::Report::default();
+
+    super::apply_code_rule(&mut collection, code_rule(super::RuleAction::Replace, "sys_a", "new_code"), &mut report);
+
+    let idx = collection.get_idx("obj1").unwrap();
+    let codes = &collection.index_mut(idx).codes;
+    assert_eq!(
+        *codes,
+        vec![
+            ("sys_a".to_string(), "new_code".to_string()),
+            ("sys_b".to_string(), "untouched".to_string()),
+        ]
+        .into_iter()
+        .collect()
+    );
+}
+
+#[test]
+fn apply_property_rule_sets_known_property() {
+    let mut collection = test_collection();
+    let mut report = super::Report::default();
+    let rule = super::ObjectPropertyRule {
+        object_type: super::ObjectType::Line,
+        object_id: "obj1".to_string(),
+        object_property: "name".to_string(),
+        property_value: "Updated".to_string(),
+    };
+
+    super::apply_property_rule(&mut collection, rule, &mut report);
+
+    let idx = collection.get_idx("obj1").unwrap();
+    assert_eq!(collection.index_mut(idx).name, "Updated");
+    assert!(report.entries.is_empty());
+}
+
+#[test]
+fn apply_property_rule_unknown_property_warns() {
+    let mut collection = test_collection();
+    let mut report = super::Report::default();
+    let rule = super::ObjectPropertyRule {
+        object_type: super::ObjectType::Line,
+        object_id: "obj1".to_string(),
+        object_property: "not_a_real_property".to_string(),
+        property_value: "x".to_string(),
+    };
+
+    super::apply_property_rule(&mut collection, rule, &mut report);
+
+    let idx = collection.get_idx("obj1").unwrap();
+    assert_eq!(collection.index_mut(idx).name, "Initial");
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(
+        report.entries[0].report_type,
+        super::ReportType::UnknownPropertyName
+    );
+}
+
+#[test]
+fn object_type_handlers_cover_all_variants() {
+    for object_type in super::ObjectType::ALL {
+        assert!(
+            super::OBJECT_TYPE_HANDLERS
+                .iter()
+                .any(|(ty, _, _)| ty == object_type),
+            "no OBJECT_TYPE_HANDLERS entry for {:?}",
+            object_type
+        );
+    }
+}
+
 #[test]
 fn bob() {
     // test to check if it's possible to import the builder
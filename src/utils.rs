@@ -33,14 +33,78 @@ where
     serializer.serialize_u8(*v as u8)
 }
 
-pub fn de_from_date_string<'de, D>(deserializer: D) -> Result<Date, D::Error>
+/// Deserializes a boolean tolerant of `0`/`1`, `true`/`false`, `yes`/`no` and `y`/`n`.
+pub fn de_from_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: ::serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        Int(u8),
+        Str(String),
+    }
+
+    match BoolOrString::deserialize(deserializer)? {
+        BoolOrString::Bool(b) => Ok(b),
+        BoolOrString::Int(0) => Ok(false),
+        BoolOrString::Int(1) => Ok(true),
+        BoolOrString::Int(i) => Err(::serde::de::Error::custom(format!(
+            "invalid boolean value {:?}",
+            i
+        ))),
+        BoolOrString::Str(s) => match s.trim().to_lowercase().as_str() {
+            "1" | "true" | "t" | "yes" | "y" => Ok(true),
+            "0" | "false" | "f" | "no" | "n" | "" => Ok(false),
+            _ => Err(::serde::de::Error::custom(format!(
+                "invalid boolean value {:?}",
+                s
+            ))),
+        },
+    }
+}
+
+/// Serializes a boolean as the canonical `0`/`1` GTFS representation, to pair with `de_from_bool`.
+pub fn ser_from_bool_as<S>(v: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: ::serde::Serializer,
+{
+    ser_from_bool(v, serializer)
+}
+
+/// Date formats tried, in order, by `de_from_date_string`.
+pub const DEFAULT_DATE_FORMATS: &[&str] = &["%Y%m%d", "%Y-%m-%d", "%Y/%m/%d"];
+
+/// Like `de_from_date_string` but with a caller-chosen ordered list of formats.
+pub fn de_from_date_string_with_formats<'de, D>(
+    deserializer: D,
+    formats: &[&str],
+) -> Result<Date, D::Error>
 where
     D: ::serde::Deserializer<'de>,
 {
     use serde::Deserialize;
     let s = String::deserialize(deserializer)?;
 
-    NaiveDate::parse_from_str(&s, "%Y%m%d").map_err(::serde::de::Error::custom)
+    formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&s, format).ok())
+        .ok_or_else(|| {
+            ::serde::de::Error::custom(format!(
+                "date {:?} does not match any of the accepted formats {:?}",
+                s, formats
+            ))
+        })
+}
+
+pub fn de_from_date_string<'de, D>(deserializer: D) -> Result<Date, D::Error>
+where
+    D: ::serde::Deserializer<'de>,
+{
+    de_from_date_string_with_formats(deserializer, DEFAULT_DATE_FORMATS)
 }
 
 pub fn ser_from_naive_date<S>(date: &Date, serializer: S) -> Result<S::Ok, S::Error>
@@ -59,3 +123,298 @@ where
     use serde::Deserialize;
     Option::<T>::deserialize(de).map(|opt| opt.unwrap_or_else(Default::default))
 }
+
+/// The kind of issue a rule application ran into.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportType {
+    ComplementaryCodeRulesRead,
+    ObjectRulesRead,
+    /// Kept distinct from `ObjectNotFound` so the pre-existing
+    /// `"complementary_object_not_found"` string in the serialized report
+    /// doesn't change for consumers filtering on it.
+    ComplementaryObjectNotFound,
+    ObjectNotFound,
+    UnknownPropertyName,
+    NoOpRemoval,
+}
+impl ReportType {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            ReportType::ComplementaryCodeRulesRead => "complementary_code_rules_read",
+            ReportType::ObjectRulesRead => "object_rules_read",
+            ReportType::ComplementaryObjectNotFound => "complementary_object_not_found",
+            ReportType::ObjectNotFound => "object_not_found",
+            ReportType::UnknownPropertyName => "unknown_property_name",
+            ReportType::NoOpRemoval => "no_op_removal",
+        }
+    }
+}
+
+/// How serious a `ReportEntry` is.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ReportEntry {
+    pub message: String,
+    pub report_type: ReportType,
+    pub severity: Severity,
+}
+
+/// Per-`ReportType`/per-`Severity` counts, serialized under `Report`'s `summary` key.
+#[derive(Serialize, Debug, Default, Clone, Copy)]
+pub struct SeverityCounts {
+    pub info: usize,
+    pub warning: usize,
+    pub error: usize,
+}
+impl SeverityCounts {
+    fn add(&mut self, severity: Severity) {
+        match severity {
+            Severity::Info => self.info += 1,
+            Severity::Warning => self.warning += 1,
+            Severity::Error => self.error += 1,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ReportSummary {
+    pub total: SeverityCounts,
+    pub by_report_type: ::std::collections::BTreeMap<&'static str, SeverityCounts>,
+}
+
+/// Collects the entries emitted while applying a batch of rules.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub entries: Vec<ReportEntry>,
+}
+
+impl Report {
+    pub fn add_info(&mut self, message: String, report_type: ReportType) {
+        self.add(message, report_type, Severity::Info);
+    }
+
+    pub fn add_warning(&mut self, message: String, report_type: ReportType) {
+        self.add(message, report_type, Severity::Warning);
+    }
+
+    pub fn add_error(&mut self, message: String, report_type: ReportType) {
+        self.add(message, report_type, Severity::Error);
+    }
+
+    pub fn add(&mut self, message: String, report_type: ReportType, severity: Severity) {
+        self.entries.push(ReportEntry {
+            message,
+            report_type,
+            severity,
+        });
+    }
+
+    pub fn summary(&self) -> ReportSummary {
+        let mut summary = ReportSummary::default();
+        for entry in &self.entries {
+            summary.total.add(entry.severity);
+            summary
+                .by_report_type
+                .entry(entry.report_type.as_str())
+                .or_insert_with(SeverityCounts::default)
+                .add(entry.severity);
+        }
+        summary
+    }
+
+    /// Whether the CLI layer applying these rules should exit non-zero.
+    pub fn has_errors(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.severity == Severity::Error)
+    }
+}
+
+impl ::serde::Serialize for Report {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct ReportView<'a> {
+            summary: ReportSummary,
+            entries: &'a [ReportEntry],
+        }
+        ReportView {
+            summary: self.summary(),
+            entries: &self.entries,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[derive(Deserialize)]
+    struct Wrapper {
+        #[serde(deserialize_with = "super::de_from_bool")]
+        v: bool,
+    }
+
+    fn de_from_bool(s: &str) -> Result<bool, serde_json::Error> {
+        serde_json::from_str::<Wrapper>(&format!("{{\"v\":{}}}", s)).map(|w| w.v)
+    }
+
+    #[test]
+    fn de_from_bool_accepts_binary_ints() {
+        assert_eq!(de_from_bool("0").unwrap(), false);
+        assert_eq!(de_from_bool("1").unwrap(), true);
+    }
+
+    #[test]
+    fn de_from_bool_rejects_non_binary_ints() {
+        assert!(de_from_bool("2").is_err());
+        assert!(de_from_bool("200").is_err());
+    }
+
+    #[test]
+    fn de_from_bool_accepts_truthy_strings() {
+        for s in &[
+            "\"true\"",
+            "\"TRUE\"",
+            "\"t\"",
+            "\"yes\"",
+            "\"y\"",
+            "\"1\"",
+            "\" True \"",
+        ] {
+            assert_eq!(de_from_bool(s).unwrap(), true, "{} should be true", s);
+        }
+    }
+
+    #[test]
+    fn de_from_bool_accepts_falsy_strings() {
+        for s in &[
+            "\"false\"",
+            "\"FALSE\"",
+            "\"f\"",
+            "\"no\"",
+            "\"n\"",
+            "\"0\"",
+            "\"\"",
+        ] {
+            assert_eq!(de_from_bool(s).unwrap(), false, "{} should be false", s);
+        }
+    }
+
+    #[test]
+    fn de_from_bool_rejects_garbage_strings() {
+        assert!(de_from_bool("\"maybe\"").is_err());
+    }
+
+    #[derive(Deserialize)]
+    struct DateWrapper {
+        #[serde(deserialize_with = "super::de_from_date_string")]
+        d: super::Date,
+    }
+
+    fn de_from_date_string(s: &str) -> Result<super::Date, serde_json::Error> {
+        serde_json::from_str::<DateWrapper>(&format!("{{\"d\":{}}}", s)).map(|w| w.d)
+    }
+
+    #[test]
+    fn de_from_date_string_accepts_canonical_format() {
+        assert_eq!(
+            de_from_date_string("\"20200102\"").unwrap(),
+            super::Date::from_ymd(2020, 1, 2)
+        );
+    }
+
+    #[test]
+    fn de_from_date_string_accepts_iso_dash_format() {
+        assert_eq!(
+            de_from_date_string("\"2020-01-02\"").unwrap(),
+            super::Date::from_ymd(2020, 1, 2)
+        );
+    }
+
+    #[test]
+    fn de_from_date_string_accepts_iso_slash_format() {
+        assert_eq!(
+            de_from_date_string("\"2020/01/02\"").unwrap(),
+            super::Date::from_ymd(2020, 1, 2)
+        );
+    }
+
+    #[test]
+    fn de_from_date_string_rejects_unmatched_format_and_lists_attempts() {
+        let err = de_from_date_string("\"not-a-date\"").unwrap_err();
+        let msg = err.to_string();
+        for format in super::DEFAULT_DATE_FORMATS {
+            assert!(msg.contains(format), "{:?} missing from error: {}", format, msg);
+        }
+    }
+
+    #[derive(Serialize)]
+    struct DateSerWrapper {
+        #[serde(serialize_with = "super::ser_from_naive_date")]
+        d: super::Date,
+    }
+
+    #[test]
+    fn ser_from_naive_date_always_emits_compact_format() {
+        let json = serde_json::to_string(&DateSerWrapper {
+            d: super::Date::from_ymd(2020, 1, 2),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"d":"20200102"}"#);
+    }
+
+    #[test]
+    fn report_summary_counts_and_has_errors() {
+        let mut report = super::Report::default();
+        report.add_info(
+            "read 3 rule(s)".to_string(),
+            super::ReportType::ComplementaryCodeRulesRead,
+        );
+        report.add_warning(
+            "object not found".to_string(),
+            super::ReportType::ObjectNotFound,
+        );
+        report.add_warning(
+            "another object not found".to_string(),
+            super::ReportType::ObjectNotFound,
+        );
+        assert!(!report.has_errors());
+
+        report.add_error(
+            "unparseable rule file".to_string(),
+            super::ReportType::ComplementaryCodeRulesRead,
+        );
+
+        let summary = report.summary();
+        assert_eq!(summary.total.info, 1);
+        assert_eq!(summary.total.warning, 2);
+        assert_eq!(summary.total.error, 1);
+
+        let code_rules_read = summary
+            .by_report_type
+            .get(super::ReportType::ComplementaryCodeRulesRead.as_str())
+            .unwrap();
+        assert_eq!(code_rules_read.info, 1);
+        assert_eq!(code_rules_read.warning, 0);
+        assert_eq!(code_rules_read.error, 1);
+
+        let object_not_found = summary
+            .by_report_type
+            .get(super::ReportType::ObjectNotFound.as_str())
+            .unwrap();
+        assert_eq!(object_not_found.warning, 2);
+        assert_eq!(object_not_found.error, 0);
+
+        assert!(report.has_errors());
+    }
+}